@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rodio::{source::SineWave, OutputStream, OutputStreamHandle, Sink, Source};
+
+use crate::{grid_len, GameSettings, PopulationStats};
+
+/// Mute toggle and master gain for the sonification layer, kept separate
+/// from `GameSettings` so audio can be disabled without touching the CA.
+pub struct AudioSettings {
+    pub muted: bool,
+    pub master_gain: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            muted: false,
+            master_gain: 0.4,
+        }
+    }
+}
+
+/// Owns the output stream and the long-lived drone sink; birth/death blips
+/// are one-shot sources played straight to the stream handle so they can
+/// overlap the drone without retriggering it. `OutputStream` wraps a raw
+/// platform audio handle that isn't `Send`/`Sync`, so this is kept as a
+/// non-send resource instead of a regular `Res`/`ResMut` one.
+pub struct LifeSynth {
+    _stream: OutputStream,
+    stream_handle: OutputStreamHandle,
+    drone: Sink,
+}
+
+/// Turns `PopulationStats` into sound each tick: live-cell density drives a
+/// drone's gain, births trigger a short high attack-decay blip, deaths a
+/// lower-pitched one, inspired by the node-graph synth wiring the bevyjam
+/// audio doc uses.
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_resource(AudioSettings::default())
+            .add_startup_system(setup_audio.system())
+            .add_system_to_stage("after_update", sonify_system.system());
+    }
+}
+
+fn setup_audio(world: &mut World) {
+    let (stream, stream_handle) =
+        OutputStream::try_default().expect("no audio output device available");
+
+    let drone = Sink::try_new(&stream_handle).expect("failed to create drone sink");
+    drone.set_volume(0.0);
+    drone.append(SineWave::new(55).repeat_infinite());
+
+    world.insert_non_send(LifeSynth {
+        _stream: stream,
+        stream_handle,
+        drone,
+    });
+}
+
+/// Gated on `PopulationStats::ticked`, a one-shot flag `simulate_system`
+/// only sets true on the frame it actually advances a generation, so this
+/// only reacts once per tick instead of every frame. `timer.0.finished`
+/// isn't safe to read here directly: it isn't cleared while the simulation
+/// is paused, so the last generation's births/deaths would otherwise replay
+/// every frame until the sim is unpaused.
+fn sonify_system(
+    audio_settings: Res<AudioSettings>,
+    stats: Res<PopulationStats>,
+    settings: Res<GameSettings>,
+    mut synth: NonSendMut<LifeSynth>,
+) {
+    if audio_settings.muted {
+        synth.drone.set_volume(0.0);
+        return;
+    }
+
+    let total_cells = grid_len(settings.room_size) as f32;
+    let density = stats.live as f32 / total_cells.max(1.0);
+    synth.drone.set_volume((density * audio_settings.master_gain).min(1.0));
+
+    if !stats.ticked {
+        return;
+    }
+
+    if stats.births > 0 {
+        play_blip(&synth.stream_handle, 440.0, audio_settings.master_gain, stats.births);
+    }
+    if stats.deaths > 0 {
+        play_blip(&synth.stream_handle, 110.0, audio_settings.master_gain, stats.deaths);
+    }
+}
+
+/// Plays a single attack-decay sine blip directly on the output stream,
+/// representing an entire generation's worth of births or deaths -- `count`
+/// only nudges the gain, so a burst of thousands of flips in one tick still
+/// costs one `play_raw` call instead of one per cell.
+fn play_blip(stream_handle: &OutputStreamHandle, frequency: f32, gain: f32, count: usize) {
+    let boosted_gain = (gain * (1.0 + (count as f32).ln())).min(1.0);
+    let mut blip = SineWave::new(frequency as u32).take_duration(Duration::from_millis(120));
+    blip.set_filter_fadeout();
+    let source = blip
+        .amplify(boosted_gain)
+        .fade_in(Duration::from_millis(5));
+
+    let _ = stream_handle.play_raw(source);
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext, EguiPlugin};
+
+use crate::audio::AudioSettings;
+use crate::pattern::SeedSource;
+use crate::gpu_life::GpuMode;
+use crate::{BoundaryMode, GameSettings, LifeGrid, PopulationStats, State, UpdateTimer};
+
+/// Whether the simulation is advancing on its own or waiting for a manual
+/// "step once" click from the inspector panel.
+pub struct SimulationControl {
+    pub paused: bool,
+    pub step_once: bool,
+    pub reseed: bool,
+}
+
+impl Default for SimulationControl {
+    fn default() -> Self {
+        SimulationControl {
+            paused: false,
+            step_once: false,
+            reseed: false,
+        }
+    }
+}
+
+/// In-game panel for tweaking `GameRules`/`GameSettings` without a recompile,
+/// following the same `bevy_egui` wiring the border-wars project uses.
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_plugin(EguiPlugin)
+            .add_resource(SimulationControl::default())
+            .add_system(inspector_ui_system.system());
+    }
+}
+
+fn inspector_ui_system(
+    egui_context: Res<EguiContext>,
+    mut settings: ResMut<GameSettings>,
+    mut control: ResMut<SimulationControl>,
+    mut timer: ResMut<UpdateTimer>,
+    grid: Res<LifeGrid>,
+    stats: Res<PopulationStats>,
+    gpu_mode: Res<GpuMode>,
+    seed_source: Res<SeedSource>,
+    mut audio_settings: ResMut<AudioSettings>,
+) {
+    let ctx = &egui_context.ctx;
+
+    egui::Window::new("Game of Life").show(ctx, |ui| {
+        match &*seed_source {
+            SeedSource::Random(seed) => ui.label(format!("Seed: random ({})", seed)),
+            SeedSource::Pattern(pattern, _) => ui.label(format!("Seed: pattern \"{}\"", pattern.name)),
+        };
+
+        ui.heading("Rules");
+        range_slider(ui, "Reproduction", &mut settings.rules.reproduction);
+        range_slider(ui, "Underpopulation", &mut settings.rules.underpopulation);
+        range_slider(ui, "Continuation", &mut settings.rules.continuation);
+        range_slider(ui, "Overpopulation", &mut settings.rules.overpopulation);
+
+        ui.heading("Boundary");
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut settings.boundary_mode, BoundaryMode::Clamped, "Clamped");
+            ui.radio_value(&mut settings.boundary_mode, BoundaryMode::Wrapping, "Wrapping");
+        });
+
+        ui.heading("Colors");
+        color_picker(ui, "Active", &mut settings.color_active);
+        color_picker(ui, "Inactive", &mut settings.color_inactive);
+
+        ui.heading("Timing");
+        let mut period = timer.0.duration;
+        if ui
+            .add(egui::Slider::f32(&mut period, 0.05..=2.0).text("Tick period (s)"))
+            .changed()
+        {
+            timer.0.duration = period;
+        }
+
+        ui.horizontal(|ui| {
+            let label = if control.paused { "Resume" } else { "Pause" };
+            if ui.button(label).clicked() {
+                control.paused = !control.paused;
+            }
+            if ui.button("Step once").clicked() {
+                control.step_once = true;
+            }
+            if ui.button("Reseed").clicked() {
+                control.reseed = true;
+            }
+        });
+
+        // In GPU mode `grid` keeps the starting generation; the current
+        // count comes from `gpu_life::readback_stats_system` instead.
+        let live = if gpu_mode.0 {
+            stats.live
+        } else {
+            grid.front.iter().filter(|&&s| s == State::Active).count()
+        };
+        ui.label(format!("Live cells: {}", live));
+
+        ui.heading("Audio");
+        ui.checkbox(&mut audio_settings.muted, "Mute");
+        ui.add(egui::Slider::f32(&mut audio_settings.master_gain, 0.0..=1.0).text("Master gain"));
+    });
+}
+
+fn range_slider(ui: &mut egui::Ui, label: &str, range: &mut std::ops::Range<u8>) {
+    let mut start = range.start as i32;
+    let mut end = range.end as i32;
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(egui::Slider::i32(&mut start, 0..=27).text("from"));
+        ui.add(egui::Slider::i32(&mut end, 0..=27).text("to"));
+    });
+    range.start = start.max(0) as u8;
+    range.end = end.max(range.start as i32) as u8;
+}
+
+fn color_picker(ui: &mut egui::Ui, label: &str, color: &mut Color) {
+    let mut rgba = [color.r, color.g, color.b, color.a];
+    ui.horizontal(|ui| {
+        ui.label(label);
+        if ui.color_edit_button_rgba_premultiplied(&mut rgba).changed() {
+            *color = Color::rgba(rgba[0], rgba[1], rgba[2], rgba[3]);
+        }
+    });
+}
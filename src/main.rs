@@ -9,7 +9,17 @@ use rand::{
     Rng, SeedableRng,
 };
 
-use std::{collections::HashMap, ops::Range};
+use std::{ops::Range, path::Path};
+
+mod audio;
+mod gpu_life;
+mod inspector;
+mod pattern;
+
+use audio::AudioPlugin;
+use gpu_life::{GpuLifePlugin, GpuMode};
+use inspector::{InspectorPlugin, SimulationControl};
+use pattern::{load_pattern, stamp_pattern, validate_pattern, SeedSource};
 
 struct GameRules {
     reproduction: Range<u8>,
@@ -18,6 +28,18 @@ struct GameRules {
     overpopulation: Range<u8>,
 }
 
+/// How neighbor offsets behave at the faces of the room.
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum BoundaryMode {
+    /// Offsets that fall outside `0..room_size` are dropped, so face cells
+    /// have fewer neighbors than interior ones (today's behavior).
+    Clamped,
+    /// Offsets wrap modulo `room_size` on each axis, so the room behaves
+    /// like a 3d torus and patterns can travel off one face and reappear on
+    /// the opposite one.
+    Wrapping,
+}
+
 struct GameSettings {
     rules: GameRules,
     room_size: u8,
@@ -25,6 +47,7 @@ struct GameSettings {
     cube_gutter: f32,
     color_active: Color,
     color_inactive: Color,
+    boundary_mode: BoundaryMode,
 }
 
 impl GameSettings {
@@ -36,9 +59,6 @@ impl GameSettings {
     }
 }
 
-#[derive(PartialEq, Debug)]
-struct ActiveNeighbors(u8);
-
 #[derive(PartialEq, Debug, Copy, Clone)]
 enum State {
     Active,
@@ -54,11 +74,72 @@ impl Distribution<State> for Standard {
     }
 }
 
-#[derive(Debug)]
-struct Neighbors(Vec<Entity>);
+/// Front/back copies of the grid's `State`, indexed
+/// `x + y * room_size + z * room_size^2`. Every tick is computed strictly
+/// from `front` into `back`, then the two are swapped, so there's no
+/// possibility of a cell reading a neighbor's already-updated state.
+struct LifeGrid {
+    front: Vec<State>,
+    back: Vec<State>,
+    changed: Vec<bool>,
+    room_size: u8,
+}
+
+impl LifeGrid {
+    fn random(room_size: u8, rng: &mut StdRng) -> Self {
+        let len = grid_len(room_size);
+        let front: Vec<State> = (0..len).map(|_| rng.gen()).collect();
+        LifeGrid {
+            back: front.clone(),
+            changed: vec![false; len],
+            front,
+            room_size,
+        }
+    }
+
+    fn inactive(room_size: u8) -> Self {
+        let len = grid_len(room_size);
+        LifeGrid {
+            front: vec![State::Inactive; len],
+            back: vec![State::Inactive; len],
+            changed: vec![false; len],
+            room_size,
+        }
+    }
+
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+}
+
+/// Material handle for each cell, indexed the same way as `LifeGrid`, so the
+/// sync system can recolor a changed cell without going through a query.
+struct CellMaterials(Vec<Handle<StandardMaterial>>);
 
 struct UpdateTimer(Timer);
 
+/// Aggregate signals for this tick, consumed by `audio::sonify_system` to
+/// drive the drone/blip synth without it needing to walk the grid itself.
+/// `ticked` is a one-shot flag: true only on the frame `simulate_system`
+/// actually advanced a generation, false otherwise (including every frame
+/// while paused), so consumers can't mistake a stale tick for a fresh one.
+#[derive(Default)]
+struct PopulationStats {
+    live: usize,
+    births: usize,
+    deaths: usize,
+    ticked: bool,
+}
+
+fn grid_len(room_size: u8) -> usize {
+    room_size as usize * room_size as usize * room_size as usize
+}
+
+/// Flatten a 3d grid coordinate into the index used by `LifeGrid`/`CellMaterials`.
+fn grid_index(x: u8, y: u8, z: u8, room_size: u8) -> usize {
+    x as usize + y as usize * room_size as usize + z as usize * room_size as usize * room_size as usize
+}
+
 fn main() {
     let settings = GameSettings {
         rules: GameRules {
@@ -72,29 +153,119 @@ fn main() {
         cube_gutter: 3.0,
         color_active: Color::rgba(1.0, 0.0, 0.0, 0.9),
         color_inactive: Color::rgba(1.0, 1.0, 1.0, 0.00),
+        boundary_mode: BoundaryMode::Clamped,
     };
 
+    let seed_source = parse_seed_source();
+    let grid = build_grid(&settings, &seed_source);
+    let cell_materials = CellMaterials(Vec::with_capacity(grid_len(settings.room_size)));
+    let gpu_mode = GpuMode(parse_gpu_mode());
+
     App::build()
         .add_resource(Msaa { samples: 8 })
         .add_resource(UpdateTimer(Timer::from_seconds(0.6, true)))
         .add_resource(settings)
+        .add_resource(grid)
+        .add_resource(seed_source)
+        .add_resource(cell_materials)
+        .add_resource(PopulationStats::default())
+        .add_resource(gpu_mode)
         .add_default_plugins()
         .add_plugin(FrameTimeDiagnosticsPlugin::default())
         .add_plugin(PrintDiagnosticsPlugin::default())
         .add_plugin(FlyCameraPlugin)
+        // Lets rules/settings be tweaked live instead of recompiling.
+        .add_plugin(InspectorPlugin)
         .add_startup_system(setup_system.system())
-        .add_system_to_stage(stage::UPDATE, count_neighbors_system.system())
+        .add_system_to_stage(stage::UPDATE, simulate_system.system())
         .add_stage_after(stage::UPDATE, "after_update")
-        .add_system_to_stage("after_update", update_state_system.system())
+        .add_system_to_stage("after_update", sync_materials_system.system())
+        .add_system_to_stage("after_update", reseed_system.system())
+        // Added after "after_update" exists, since it schedules a system
+        // onto that stage as soon as the plugin is built.
+        // Turns live-cell/birth/death counts into a drone + blips.
+        .add_plugin(AudioPlugin)
+        // Also needs "after_update" to exist, for its own stats readback;
+        // only does real work when `--gpu` was passed (see `GpuMode`).
+        .add_plugin(GpuLifePlugin)
         .run();
 }
 
+/// Reads `--pattern <file> [--offset x y z]` or `--seed <n>` off the command
+/// line to decide how the grid should be seeded. Defaults to a random fill
+/// with a freshly drawn seed (still reported, so the run can be reproduced
+/// later).
+fn parse_seed_source() -> SeedSource {
+    let args: Vec<String> = std::env::args().collect();
+
+    if let Some(path) = args.iter().position(|a| a == "--pattern").and_then(|i| args.get(i + 1)) {
+        let offset = parse_offset(&args).unwrap_or((0, 0, 0));
+        match load_pattern(Path::new(path)) {
+            Ok(pattern) => return SeedSource::Pattern(pattern, offset),
+            Err(err) => eprintln!("{}; falling back to a random fill", err),
+        }
+    }
+
+    let seed = args
+        .iter()
+        .position(|a| a == "--seed")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or_else(|| StdRng::from_entropy().gen());
+
+    SeedSource::Random(seed)
+}
+
+/// Whether `--gpu` was passed, switching the grid over to the GPU
+/// compute/instanced-draw path in `gpu_life` instead of the CPU flat-buffer
+/// one, so `room_size` can scale well past what one entity per cell allows.
+fn parse_gpu_mode() -> bool {
+    std::env::args().any(|a| a == "--gpu")
+}
+
+/// Parses `--offset x y z` so a pattern can be stamped somewhere other than
+/// the grid's origin.
+fn parse_offset(args: &[String]) -> Option<(u8, u8, u8)> {
+    let i = args.iter().position(|a| a == "--offset")?;
+    let x = args.get(i + 1)?.parse().ok()?;
+    let y = args.get(i + 2)?.parse().ok()?;
+    let z = args.get(i + 3)?.parse().ok()?;
+    Some((x, y, z))
+}
+
+/// Builds the initial `LifeGrid` from `seed_source`, validating a pattern's
+/// coordinates against `settings.room_size` and falling back to an empty
+/// grid (reporting the offending coordinates) if it doesn't fit.
+fn build_grid(settings: &GameSettings, seed_source: &SeedSource) -> LifeGrid {
+    match seed_source {
+        SeedSource::Random(seed) => {
+            println!("Seeding random fill with seed {}", seed);
+            let mut rng = StdRng::seed_from_u64(*seed);
+            LifeGrid::random(settings.room_size, &mut rng)
+        }
+        SeedSource::Pattern(pattern, offset) => {
+            let mut grid = LifeGrid::inactive(settings.room_size);
+            match validate_pattern(pattern, settings.room_size, *offset) {
+                Ok(()) => stamp_pattern(&mut grid, pattern, *offset),
+                Err(offending) => eprintln!(
+                    "pattern {:?} does not fit in room_size {}: offending cells {:?}",
+                    pattern.name, settings.room_size, offending
+                ),
+            }
+            grid
+        }
+    }
+}
+
 /// Setup the 3d Grid entities, Camera, Light
 fn setup_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut cell_materials: ResMut<CellMaterials>,
     settings: Res<GameSettings>,
+    grid: Res<LifeGrid>,
+    gpu_mode: Res<GpuMode>,
 ) {
     let c = settings.room_size as f32 * settings.cube_size;
     // add entities to the world
@@ -120,154 +291,274 @@ fn setup_system(
             ..Default::default()
         });
 
-    let mut rng = StdRng::from_entropy();
+    // In GPU mode `gpu_life::setup_gpu_life` draws the whole room as one
+    // instanced mesh instead; spawning one entity per cell here as well
+    // would defeat the point of moving the grid off the CPU.
+    if gpu_mode.0 {
+        return;
+    }
 
     for z in 0..settings.room_size {
         for y in 0..settings.room_size {
             for x in 0..settings.room_size {
-                let state: State = rng.gen();
-                let entity = make_entity(x, y, z);
-                println!("Creating entity {:?}-{:?}-{:?} => {:?} is {:?}", x, y, z, entity, state);
-
-                commands
-                    .spawn_as_entity(
-                        entity,
-                        (
-                            state,
-                            ActiveNeighbors(0),
-                            make_neighbors_component(x, y, z, settings.room_size),
-                        ),
-                    )
-                    .with_bundle(PbrComponents {
-                        mesh: meshes.add(Mesh::from(shape::Cube {
-                            size: settings.cube_size,
-                        })),
-                        material: materials.add(StandardMaterial {
-                            albedo: settings.map_state_to_color(&state),
-                            ..Default::default()
-                        }),
-                        draw: Draw {
-                            is_transparent: true,
-                            ..Default::default()
-                        },
-                        translation: Translation::new(
-                            x as f32 * settings.cube_size * settings.cube_gutter,
-                            y as f32 * settings.cube_size * settings.cube_gutter,
-                            z as f32 * settings.cube_size * settings.cube_gutter,
-                        ),
+                let index = grid_index(x, y, z, settings.room_size);
+                let state = grid.front[index];
+                let material = materials.add(StandardMaterial {
+                    albedo: settings.map_state_to_color(&state),
+                    ..Default::default()
+                });
+                cell_materials.0.push(material);
+
+                commands.spawn(PbrComponents {
+                    mesh: meshes.add(Mesh::from(shape::Cube {
+                        size: settings.cube_size,
+                    })),
+                    material,
+                    draw: Draw {
+                        is_transparent: true,
                         ..Default::default()
-                    });
+                    },
+                    translation: Translation::new(
+                        x as f32 * settings.cube_size * settings.cube_gutter,
+                        y as f32 * settings.cube_size * settings.cube_gutter,
+                        z as f32 * settings.cube_size * settings.cube_gutter,
+                    ),
+                    ..Default::default()
+                });
             }
         }
     }
 }
 
-/// Create Entity based on x,y,z coordinates to simplify neighbor lookup
-fn make_entity(x: u8, y: u8, z: u8) -> Entity {
-    let mut id: u32 = 0;
-    id = (id | x as u32) << 8;
-    id = (id | y as u32) << 8;
-    id = (id | z as u32) << 8;
-    Entity::from_id(id)
+/// Wrap `v` modulo `room_size`, used by `BoundaryMode::Wrapping`.
+fn wrap(v: i16, room_size: u8) -> i16 {
+    let size = room_size as i16;
+    ((v % size) + size) % size
 }
 
-/// Create a neighbors component for a cell
-fn make_neighbors_component(x: u8, y: u8, z: u8, room_size: u8) -> Neighbors {
-    let mut neighbors = Vec::with_capacity(26);
+/// Count active neighbors of `(x, y, z)` directly off `buffer`. Under
+/// `BoundaryMode::Clamped`, offsets outside `0..room_size` are dropped so
+/// face cells have fewer neighbors; under `BoundaryMode::Wrapping`, offsets
+/// wrap modulo `room_size` on each axis so the room behaves like a torus.
+fn count_active_neighbors(
+    buffer: &[State],
+    x: u8,
+    y: u8,
+    z: u8,
+    room_size: u8,
+    boundary_mode: BoundaryMode,
+) -> u8 {
     let bounds: Range<i16> = 0..room_size as i16;
-
-    let x = x as i16;
-    let y = y as i16;
-    let z = z as i16;
+    let (x, y, z) = (x as i16, y as i16, z as i16);
+    let mut active = 0u8;
 
     for dz in z - 1..=z + 1 {
         for dy in y - 1..=y + 1 {
             for dx in x - 1..=x + 1 {
-                if bounds.contains(&dx)
-                    && bounds.contains(&dy)
-                    && bounds.contains(&dz)
-                    && !(x == dx && y == dy && z == dz)
-                {
-                    neighbors.push(make_entity(dx as u8, dy as u8, dz as u8));
+                if x == dx && y == dy && z == dz {
+                    continue;
+                }
+
+                let (nx, ny, nz) = match boundary_mode {
+                    BoundaryMode::Clamped => {
+                        if bounds.contains(&dx) && bounds.contains(&dy) && bounds.contains(&dz) {
+                            (dx, dy, dz)
+                        } else {
+                            continue;
+                        }
+                    }
+                    BoundaryMode::Wrapping => {
+                        (wrap(dx, room_size), wrap(dy, room_size), wrap(dz, room_size))
+                    }
+                };
+
+                let index = grid_index(nx as u8, ny as u8, nz as u8, room_size);
+                if buffer[index] == State::Active {
+                    active += 1;
                 }
             }
         }
     }
 
-    Neighbors(neighbors)
+    active
+}
+
+fn next_state(rules: &GameRules, current: State, active_neighbors: u8) -> State {
+    match current {
+        State::Active => {
+            if rules.underpopulation.contains(&active_neighbors) {
+                State::Inactive
+            } else if rules.continuation.contains(&active_neighbors) {
+                State::Active
+            } else if rules.overpopulation.contains(&active_neighbors) {
+                State::Inactive
+            } else {
+                State::Active
+            }
+        }
+        State::Inactive => {
+            if rules.reproduction.contains(&active_neighbors) {
+                State::Active
+            } else {
+                State::Inactive
+            }
+        }
+    }
 }
 
-fn count_neighbors_system(
+/// Computes every cell's next state from `grid.front` into `grid.back`,
+/// recording which indices flipped so `sync_materials_system` only has to
+/// touch the cells that actually changed.
+fn simulate_system(
     time: Res<Time>,
     mut timer: ResMut<UpdateTimer>,
-    mut cell_query: Query<(&Neighbors, &mut ActiveNeighbors)>,
-    neighbor_query: Query<&State>,
+    mut control: ResMut<SimulationControl>,
+    settings: Res<GameSettings>,
+    mut grid: ResMut<LifeGrid>,
+    mut stats: ResMut<PopulationStats>,
+    gpu_mode: Res<GpuMode>,
 ) {
-    timer.0.tick(time.delta_seconds);
-
-    if timer.0.finished {
-        let mut cache: HashMap<Entity, State> = HashMap::new();
-
-        for (neighbors, mut active_neighbors) in &mut cell_query.iter() {
-            active_neighbors.0 = neighbors
-                .0
-                .iter()
-                .map(|&entity| {
-                    let mut s = State::Active;
-                    // Check if value is in cache
-                    if let Some(state) = cache.get(&entity) {
-                        //println!("cache hit");
-                        return *state;
-                    }
-                    // Query from world
-                    else if let Ok(state) = neighbor_query.get::<State>(entity) {
-                        s = *state;
-                    }
-                    cache.insert(entity, s);
-                    s
-                })
-                .filter(|&state| {
-                    state == State::Active
-                })
-                .count() as u8;
+    // `gpu_life::LifeComputeNode` advances the grid on the GPU instead, and
+    // `gpu_life::readback_stats_system` reports `PopulationStats` for it.
+    if gpu_mode.0 {
+        return;
+    }
+
+    // Cleared up front so it only reads true on the one frame a generation
+    // actually advances; every early return below (including "paused")
+    // leaves it false.
+    stats.ticked = false;
+
+    if !control.paused || control.step_once {
+        timer.0.tick(time.delta_seconds);
+    }
+
+    if !((timer.0.finished && !control.paused) || control.step_once) {
+        return;
+    }
+    control.step_once = false;
+    stats.ticked = true;
+
+    let room_size = settings.room_size;
+    let mut live = 0;
+    let mut births = 0;
+    let mut deaths = 0;
+
+    for z in 0..room_size {
+        for y in 0..room_size {
+            for x in 0..room_size {
+                let index = grid_index(x, y, z, room_size);
+                let active_neighbors = count_active_neighbors(
+                    &grid.front,
+                    x,
+                    y,
+                    z,
+                    room_size,
+                    settings.boundary_mode,
+                );
+                let current = grid.front[index];
+                let next = next_state(&settings.rules, current, active_neighbors);
+                grid.changed[index] = next != current;
+                grid.back[index] = next;
+
+                match (current, next) {
+                    (State::Inactive, State::Active) => births += 1,
+                    (State::Active, State::Inactive) => deaths += 1,
+                    _ => {}
+                }
+                if next == State::Active {
+                    live += 1;
+                }
+            }
         }
     }
+
+    stats.live = live;
+    stats.births = births;
+    stats.deaths = deaths;
 }
 
-fn update_state_system(
+/// Recolors only the cells `simulate_system` marked as changed this tick,
+/// then swaps the front/back buffers for the next one.
+fn sync_materials_system(
     settings: Res<GameSettings>,
+    cell_materials: Res<CellMaterials>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    mut cells: Query<(
-        Mutated<ActiveNeighbors>,
-        &mut State,
-        &Handle<StandardMaterial>,
-    )>,
+    mut grid: ResMut<LifeGrid>,
+    stats: Res<PopulationStats>,
+    gpu_mode: Res<GpuMode>,
 ) {
-    for (active_neighbors, mut state, material_handle) in &mut cells.iter() {
-        if *state == State::Active {
-            if settings
-                .rules
-                .underpopulation
-                .contains(&(*active_neighbors).0)
-            {
-                *state = State::Inactive;
-            } else if settings.rules.continuation.contains(&(*active_neighbors).0) {
-                // Do nothing atm
-            } else if settings
-                .rules
-                .overpopulation
-                .contains(&(*active_neighbors).0)
-            {
-                *state = State::Inactive;
+    // No per-cell materials exist in GPU mode; the instanced fragment
+    // shader reads cell state straight off the GPU buffer instead.
+    if gpu_mode.0 {
+        return;
+    }
+
+    // `back` only holds a new generation on a frame that ticked; swapping
+    // on any other frame would flip the grid back to the previous one.
+    if !stats.ticked {
+        return;
+    }
+
+    for index in 0..grid.back.len() {
+        if grid.changed[index] {
+            let material = materials.get_mut(&cell_materials.0[index]).unwrap();
+            material.albedo = settings.map_state_to_color(&grid.back[index]);
+        }
+    }
+
+    grid.swap();
+}
+
+/// Re-seeds the grid in place when the inspector's "reseed" button is
+/// pressed, without despawning/respawning any entities: a random source
+/// redraws from its recorded seed, a pattern source is re-stamped from
+/// scratch.
+fn reseed_system(
+    settings: Res<GameSettings>,
+    mut control: ResMut<SimulationControl>,
+    mut seed_source: ResMut<SeedSource>,
+    cell_materials: Res<CellMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut grid: ResMut<LifeGrid>,
+    gpu_mode: Res<GpuMode>,
+) {
+    if !control.reseed {
+        return;
+    }
+    control.reseed = false;
+
+    // Reseeding the GPU buffer would need its own compute dispatch; not
+    // wired up yet, so leave the GPU path's current generation in place.
+    if gpu_mode.0 {
+        return;
+    }
+
+    match &mut *seed_source {
+        SeedSource::Random(seed) => {
+            // `SeedSource::Random(seed)` and the inspector's "Seed: random
+            // (N)" label promise that `seed` fully determines the grid, so
+            // this draws from a seed rather than fresh entropy. The next
+            // seed is drawn from the current one's stream and stored back
+            // before filling from it, so repeated reseeds don't just replay
+            // the same fill, and the label always matches what was actually
+            // drawn.
+            let next: u64 = StdRng::seed_from_u64(*seed).gen();
+            *seed = next;
+            let mut rng = StdRng::seed_from_u64(next);
+            for state in grid.front.iter_mut() {
+                *state = rng.gen();
             }
-        } else {
-            if settings.rules.reproduction.contains(&(*active_neighbors).0) {
-                *state = State::Active;
+        }
+        SeedSource::Pattern(pattern, offset) => {
+            if validate_pattern(pattern, settings.room_size, *offset).is_ok() {
+                stamp_pattern(&mut grid, pattern, *offset);
             }
         }
+    }
 
-        let material = materials.get_mut(&material_handle).unwrap();
-
-        material.albedo = settings.map_state_to_color(&state);
+    for index in 0..grid.front.len() {
+        let material = materials.get_mut(&cell_materials.0[index]).unwrap();
+        material.albedo = settings.map_state_to_color(&grid.front[index]);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,81 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::{grid_index, LifeGrid, State};
+
+/// A named, reusable arrangement of active cells, loaded from a RON pattern
+/// file. Coordinates are relative to the pattern's own origin; `stamp_pattern`
+/// shifts them by the chosen offset before writing into the grid.
+#[derive(Deserialize, Debug)]
+pub struct Pattern {
+    pub name: String,
+    pub cells: Vec<(u8, u8, u8)>,
+}
+
+/// Where the initial grid comes from: the existing random fill (reseedable
+/// via a fixed `seed` so a run can be reproduced) or a named `Pattern`
+/// stamped in at an offset.
+pub enum SeedSource {
+    Random(u64),
+    Pattern(Pattern, (u8, u8, u8)),
+}
+
+/// Reads and parses a pattern file. Pattern files are plain RON, e.g.:
+/// `(name: "glider", cells: [(1, 0, 0), (2, 1, 0), (0, 2, 0), (1, 2, 0), (2, 2, 0)])`
+pub fn load_pattern(path: &Path) -> Result<Pattern, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read pattern file {:?}: {}", path, err))?;
+    ron::de::from_str(&contents)
+        .map_err(|err| format!("failed to parse pattern file {:?}: {}", path, err))
+}
+
+/// Checks that every cell in `pattern`, once shifted by `offset`, still
+/// lands inside `0..room_size` on every axis. Returns the offending
+/// pattern-local coordinates (not yet offset) if it doesn't fit.
+pub fn validate_pattern(
+    pattern: &Pattern,
+    room_size: u8,
+    offset: (u8, u8, u8),
+) -> Result<(), Vec<(u8, u8, u8)>> {
+    let room_size = room_size as u16;
+    let offending: Vec<(u8, u8, u8)> = pattern
+        .cells
+        .iter()
+        .copied()
+        .filter(|&(x, y, z)| {
+            let ox = x as u16 + offset.0 as u16;
+            let oy = y as u16 + offset.1 as u16;
+            let oz = z as u16 + offset.2 as u16;
+            ox >= room_size || oy >= room_size || oz >= room_size
+        })
+        .collect();
+
+    if offending.is_empty() {
+        Ok(())
+    } else {
+        Err(offending)
+    }
+}
+
+/// Sets every cell to `State::Inactive`, then activates `pattern`'s cells
+/// shifted by `offset`. Callers should run `validate_pattern` first, but
+/// any coordinate that still doesn't fit is skipped rather than panicking.
+pub fn stamp_pattern(grid: &mut LifeGrid, pattern: &Pattern, offset: (u8, u8, u8)) {
+    for state in grid.front.iter_mut() {
+        *state = State::Inactive;
+    }
+
+    let room_size = grid.room_size as u16;
+    for &(x, y, z) in &pattern.cells {
+        let ox = x as u16 + offset.0 as u16;
+        let oy = y as u16 + offset.1 as u16;
+        let oz = z as u16 + offset.2 as u16;
+        if ox >= room_size || oy >= room_size || oz >= room_size {
+            continue;
+        }
+
+        let index = grid_index(ox as u8, oy as u8, oz as u8, grid.room_size);
+        grid.front[index] = State::Active;
+    }
+}